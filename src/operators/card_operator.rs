@@ -0,0 +1,27 @@
+use std::env;
+
+use qdrant_client::client::{QdrantClient, QdrantClientConfig};
+use serde::Serialize;
+
+use super::qdrant_operator::ScoreDetails;
+use crate::errors::DefaultError;
+
+pub async fn get_qdrant_connection() -> Result<QdrantClient, DefaultError> {
+    let qdrant_url =
+        env::var("QDRANT_URL").unwrap_or_else(|_| "http://localhost:6334".to_string());
+    let qdrant_api_key = env::var("QDRANT_API_KEY").ok();
+
+    let mut config = QdrantClientConfig::from_url(&qdrant_url);
+    config.api_key = qdrant_api_key;
+
+    QdrantClient::new(Some(config)).map_err(|_err| DefaultError {
+        message: "Failed to connect to Qdrant",
+    })
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SearchResult {
+    pub point_id: uuid::Uuid,
+    pub score: f32,
+    pub score_details: ScoreDetails,
+}
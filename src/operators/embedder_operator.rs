@@ -0,0 +1,205 @@
+use std::collections::{HashMap, VecDeque};
+use std::env;
+use std::future::Future;
+use std::sync::{Mutex, OnceLock};
+
+use crate::errors::DefaultError;
+
+/// Max distinct texts kept cached at once. Each `text-embedding-ada-002` vector is
+/// 1536 f32s (~6KB); at this capacity the cache tops out around a few megabytes
+/// regardless of how many unique cards or queries flow through the process.
+const EMBEDDING_CACHE_CAPACITY: usize = 500;
+
+/// Fixed-capacity cache that evicts the least recently used entry once full, so
+/// embedding a steady stream of unique card/query text can't grow memory without
+/// bound.
+struct LruCache {
+    capacity: usize,
+    entries: HashMap<String, Vec<f32>>,
+    usage_order: VecDeque<String>,
+}
+
+impl LruCache {
+    fn new(capacity: usize) -> Self {
+        LruCache {
+            capacity,
+            entries: HashMap::new(),
+            usage_order: VecDeque::new(),
+        }
+    }
+
+    fn get(&mut self, key: &str) -> Option<Vec<f32>> {
+        let embedding_vector = self.entries.get(key)?.clone();
+        self.touch(key);
+        Some(embedding_vector)
+    }
+
+    fn insert(&mut self, key: String, embedding_vector: Vec<f32>) {
+        if self.entries.contains_key(&key) {
+            self.entries.insert(key.clone(), embedding_vector);
+            self.touch(&key);
+            return;
+        }
+
+        if self.entries.len() >= self.capacity {
+            if let Some(least_recently_used) = self.usage_order.pop_front() {
+                self.entries.remove(&least_recently_used);
+            }
+        }
+
+        self.usage_order.push_back(key.clone());
+        self.entries.insert(key, embedding_vector);
+    }
+
+    fn touch(&mut self, key: &str) {
+        if let Some(position) = self.usage_order.iter().position(|cached_key| cached_key == key) {
+            let cached_key = self.usage_order.remove(position).unwrap();
+            self.usage_order.push_back(cached_key);
+        }
+    }
+}
+
+fn embedding_cache() -> &'static Mutex<LruCache> {
+    static CACHE: OnceLock<Mutex<LruCache>> = OnceLock::new();
+    CACHE.get_or_init(|| Mutex::new(LruCache::new(EMBEDDING_CACHE_CAPACITY)))
+}
+
+/// Calls the configured embedding model server and returns the embedding for `text`.
+/// The endpoint and model are configurable via `EMBEDDING_SERVER_ORIGIN` and
+/// `EMBEDDING_MODEL_NAME` so the embedder can be swapped without touching callers.
+/// `EMBEDDING_SERVER_API_KEY`, if set, is sent as a bearer token - required by the
+/// default origin (OpenAI's API) and most other hosted embedding providers.
+async fn call_embedding_server(text: &str) -> Result<Vec<f32>, DefaultError> {
+    let origin = env::var("EMBEDDING_SERVER_ORIGIN")
+        .unwrap_or_else(|_| "https://api.openai.com/v1".to_string());
+    let model =
+        env::var("EMBEDDING_MODEL_NAME").unwrap_or_else(|_| "text-embedding-ada-002".to_string());
+    let api_key = env::var("EMBEDDING_SERVER_API_KEY").ok();
+
+    let client = reqwest::Client::new();
+    let mut request = client
+        .post(format!("{}/embeddings", origin))
+        .json(&serde_json::json!({ "input": text, "model": model }));
+
+    if let Some(api_key) = api_key {
+        request = request.bearer_auth(api_key);
+    }
+
+    let response = request.send().await.map_err(|_e| DefaultError {
+        message: "Failed to reach embedding server",
+    })?;
+
+    let body: serde_json::Value = response.json().await.map_err(|_e| DefaultError {
+        message: "Failed to parse embedding server response",
+    })?;
+
+    body["data"][0]["embedding"]
+        .as_array()
+        .map(|values| {
+            values
+                .iter()
+                .filter_map(|value| value.as_f64().map(|value| value as f32))
+                .collect()
+        })
+        .ok_or(DefaultError {
+            message: "Embedding server response did not contain an embedding",
+        })
+}
+
+/// Embeds `text`, reusing a cached vector when the exact same input was embedded
+/// before instead of calling the embedding server again. `fetch_embedding` is the
+/// seam that lets tests exercise the cache without a live embedding server.
+async fn embed_text_with<F, Fut>(text: &str, fetch_embedding: F) -> Result<Vec<f32>, DefaultError>
+where
+    F: Fn(&str) -> Fut,
+    Fut: Future<Output = Result<Vec<f32>, DefaultError>>,
+{
+    if let Some(cached_embedding) = embedding_cache().lock().unwrap().get(text) {
+        return Ok(cached_embedding);
+    }
+
+    let embedding_vector = fetch_embedding(text).await?;
+
+    embedding_cache()
+        .lock()
+        .unwrap()
+        .insert(text.to_string(), embedding_vector.clone());
+
+    Ok(embedding_vector)
+}
+
+/// Embeds `text`, reusing a cached vector when the exact same input was embedded
+/// before instead of calling the embedding server again.
+pub async fn embed_text(text: &str) -> Result<Vec<f32>, DefaultError> {
+    embed_text_with(text, call_embedding_server).await
+}
+
+#[cfg(test)]
+mod embed_text_tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    use super::*;
+
+    fn counting_fetcher(
+        embedding_vector: Vec<f32>,
+    ) -> (impl Fn(&str) -> std::future::Ready<Result<Vec<f32>, DefaultError>>, Arc<AtomicUsize>) {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counted_calls = calls.clone();
+
+        let fetch = move |_: &str| {
+            counted_calls.fetch_add(1, Ordering::SeqCst);
+            std::future::ready(Ok(embedding_vector.clone()))
+        };
+
+        (fetch, calls)
+    }
+
+    #[tokio::test]
+    async fn caches_repeat_calls_for_the_same_text() {
+        let (fetch, calls) = counting_fetcher(vec![1.0, 2.0, 3.0]);
+        let text = "embed_text_tests::caches_repeat_calls_for_the_same_text";
+
+        let first = embed_text_with(text, &fetch).await.unwrap();
+        let second = embed_text_with(text, &fetch).await.unwrap();
+
+        assert_eq!(first, second);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn distinct_text_is_not_served_from_another_entrys_cache() {
+        let (fetch, calls) = counting_fetcher(vec![1.0, 2.0, 3.0]);
+
+        embed_text_with(
+            "embed_text_tests::distinct_text_is_not_served_from_another_entrys_cache/a",
+            &fetch,
+        )
+        .await
+        .unwrap();
+        embed_text_with(
+            "embed_text_tests::distinct_text_is_not_served_from_another_entrys_cache/b",
+            &fetch,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn evicts_the_least_recently_used_entry_once_over_capacity() {
+        let mut cache = LruCache::new(2);
+
+        cache.insert("a".to_string(), vec![1.0]);
+        cache.insert("b".to_string(), vec![2.0]);
+        // Touch "a" so "b" becomes the least recently used entry.
+        assert!(cache.get("a").is_some());
+
+        cache.insert("c".to_string(), vec![3.0]);
+
+        assert!(cache.get("b").is_none());
+        assert!(cache.get("a").is_some());
+        assert!(cache.get("c").is_some());
+    }
+}
@@ -1,12 +1,51 @@
+use std::collections::HashMap;
+
 use qdrant_client::qdrant::{
-    point_id::PointIdOptions, points_selector::PointsSelectorOneOf, Filter, PointId, PointStruct,
-    PointsIdsList, PointsSelector, SearchPoints, WithPayloadSelector, WithVectorsSelector,
+    point_id::PointIdOptions, points_selector::PointsSelectorOneOf, Condition, Filter, PointId,
+    PointStruct, PointsIdsList, PointsSelector, ScrollPoints, SearchPoints, WithPayloadSelector,
+    WithVectorsSelector,
 };
+use serde::Serialize;
 use serde_json::json;
 
 use super::card_operator::{get_qdrant_connection, SearchResult};
+use super::embedder_operator::embed_text;
 use crate::errors::{DefaultError, ServiceError};
 
+/// Single source of truth for the Qdrant collection name, so reads and writes
+/// can't drift apart the way `"debate_cards"` and `"debate-cards"` once did.
+const COLLECTION_NAME: &str = "debate_cards";
+
+const PAGE_SIZE: u64 = 10;
+/// Smoothing constant for Reciprocal Rank Fusion; 60 is the value used in the
+/// original RRF paper and is a reasonable default when result lists are short.
+const RRF_K: f32 = 60.0;
+
+/// Per-signal breakdown of how a `SearchResult`'s final score was derived.
+/// Each field is `None` when that ranking signal didn't contribute, e.g.
+/// `keyword_score` is `None` for a result only found through a pure vector
+/// search.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ScoreDetails {
+    /// Raw cosine similarity returned by Qdrant's vector search.
+    pub vector_similarity: Option<f32>,
+    /// Contribution from the keyword/full-text search path.
+    pub keyword_score: Option<f32>,
+    /// Final fused score once Reciprocal Rank Fusion has combined the above.
+    pub rrf_score: Option<f32>,
+}
+
+fn build_card_payload(private: bool, author_id: Option<uuid::Uuid>) -> qdrant_client::qdrant::Payload {
+    match private {
+        true => {
+            json!({"private": true, "authors": vec![author_id.unwrap_or_default().to_string()]})
+                .try_into()
+                .expect("A json! Value must always be a valid Payload")
+        }
+        false => json!({}).try_into().expect("A json! Value must always be a valid Payload"),
+    }
+}
+
 pub async fn create_new_qdrant_point_query(
     point_id: uuid::Uuid,
     embedding_vector: Vec<f32>,
@@ -17,25 +56,86 @@ pub async fn create_new_qdrant_point_query(
         .await
         .map_err(|err| ServiceError::BadRequest(err.message.into()))?;
 
-    let payload = match private {
-        true => {
-            json!({"private": true, "authors": vec![author_id.unwrap_or_default().to_string()]})
-                .try_into()
-                .expect("A json! Value must always be a valid Payload")
-        }
-        false => json!({}).try_into().expect("A json! Value must always be a valid Payload"),
-    };
+    let payload = build_card_payload(private, author_id);
 
     let point = PointStruct::new(point_id.clone().to_string(), embedding_vector, payload);
 
     qdrant
-        .upsert_points_blocking("debate_cards".to_string(), vec![point], None)
+        .upsert_points_blocking(COLLECTION_NAME.to_string(), vec![point], None)
         .await
         .map_err(|_err| ServiceError::BadRequest("Failed inserting card to qdrant".into()))?;
 
     Ok(())
 }
 
+/// How many points are sent per `upsert_points_blocking` call when batch-inserting.
+const BATCH_CHUNK_SIZE: usize = 250;
+
+fn point_struct_for(
+    point_id: uuid::Uuid,
+    embedding_vector: &[f32],
+    private: bool,
+    author_id: Option<uuid::Uuid>,
+) -> PointStruct {
+    PointStruct::new(
+        point_id.to_string(),
+        embedding_vector.to_vec(),
+        build_card_payload(private, author_id),
+    )
+}
+
+/// Upserts many points in a single connection, chunked to keep individual Qdrant
+/// requests a reasonable size. Each `(point_id, embedding_vector, private, author_id)`
+/// tuple mirrors the parameters of `create_new_qdrant_point_query`.
+///
+/// Returns the outcome of every point keyed by its id. Qdrant's blocking upsert is
+/// all-or-nothing per chunk, so when a chunk-wide upsert fails, each point in that
+/// chunk is retried individually to isolate which ones actually failed - a single
+/// bad point no longer drags down the rest of its chunk.
+pub async fn create_new_qdrant_points_batch(
+    points: Vec<(uuid::Uuid, Vec<f32>, bool, Option<uuid::Uuid>)>,
+) -> Result<HashMap<uuid::Uuid, Result<(), DefaultError>>, DefaultError> {
+    let qdrant = get_qdrant_connection().await?;
+
+    let mut results = HashMap::with_capacity(points.len());
+
+    for chunk in points.chunks(BATCH_CHUNK_SIZE) {
+        let chunk_points: Vec<PointStruct> = chunk
+            .iter()
+            .map(|(point_id, embedding_vector, private, author_id)| {
+                point_struct_for(*point_id, embedding_vector, *private, *author_id)
+            })
+            .collect();
+
+        let chunk_result = qdrant
+            .upsert_points_blocking(COLLECTION_NAME.to_string(), chunk_points, None)
+            .await;
+
+        if chunk_result.is_ok() {
+            for (point_id, ..) in chunk {
+                results.insert(*point_id, Ok(()));
+            }
+            continue;
+        }
+
+        for (point_id, embedding_vector, private, author_id) in chunk {
+            let point = point_struct_for(*point_id, embedding_vector, *private, *author_id);
+
+            let outcome = qdrant
+                .upsert_points_blocking(COLLECTION_NAME.to_string(), vec![point], None)
+                .await
+                .map(|_| ())
+                .map_err(|_err| DefaultError {
+                    message: "Failed inserting card to qdrant",
+                });
+
+            results.insert(*point_id, outcome);
+        }
+    }
+
+    Ok(results)
+}
+
 pub async fn update_qdrant_point_private_query(
     point_id: uuid::Uuid,
     private: bool,
@@ -53,7 +153,7 @@ pub async fn update_qdrant_point_private_query(
 
     let current_point_vec = qdrant
         .get_points(
-            "debate_cards",
+            COLLECTION_NAME,
             &qdrant_point_id,
             Some(WithVectorsSelector {
                 selector_options: None,
@@ -126,7 +226,7 @@ pub async fn update_qdrant_point_private_query(
 
     qdrant
         .set_payload(
-            "debate-cards",
+            COLLECTION_NAME,
             &points_selector,
             payload
                 .try_into()
@@ -141,19 +241,33 @@ pub async fn update_qdrant_point_private_query(
     Ok(())
 }
 
-pub async fn search_qdrant_query(
+/// Pure vector (semantic) similarity search against the `debate_cards` collection.
+async fn search_qdrant_vector_query(
     page: u64,
     filter: Filter,
     embedding_vector: Vec<f32>,
+) -> Result<Vec<SearchResult>, DefaultError> {
+    search_qdrant_vector_candidates(filter, embedding_vector, PAGE_SIZE, (page - 1) * PAGE_SIZE)
+        .await
+}
+
+/// Lower-level vector search that takes an explicit `limit`/`offset` rather than a
+/// page number, so callers that need an unpaginated candidate pool (e.g. hybrid
+/// fusion) aren't forced through page-sized windows.
+async fn search_qdrant_vector_candidates(
+    filter: Filter,
+    embedding_vector: Vec<f32>,
+    limit: u64,
+    offset: u64,
 ) -> Result<Vec<SearchResult>, DefaultError> {
     let qdrant = get_qdrant_connection().await?;
 
     let data = qdrant
         .search_points(&SearchPoints {
-            collection_name: "debate_cards".to_string(),
+            collection_name: COLLECTION_NAME.to_string(),
             vector: embedding_vector,
-            limit: 10,
-            offset: Some((page - 1) * 10),
+            limit,
+            offset: Some(offset),
             with_payload: None,
             filter: Some(filter),
             ..Default::default()
@@ -170,10 +284,327 @@ pub async fn search_qdrant_query(
             PointIdOptions::Uuid(id) => Some(SearchResult {
                 score: point.score,
                 point_id: uuid::Uuid::parse_str(&id).ok()?,
+                score_details: ScoreDetails {
+                    vector_similarity: Some(point.score),
+                    ..Default::default()
+                },
             }),
             PointIdOptions::Num(_) => None,
         })
         .collect();
 
     Ok(point_ids)
-}
\ No newline at end of file
+}
+
+/// Keyword/full-text search against the `debate_cards` collection, matched on the
+/// `content` payload field. Requires a text index on that field in the collection
+/// schema. Takes an explicit `limit` rather than a page number and always reads
+/// from the start of the result set, so it can supply the same unpaginated
+/// candidate pool to `reciprocal_rank_fusion` that the vector side uses. Qdrant's
+/// scroll API does not rank by text relevance, so the returned order reflects
+/// storage order rather than a BM25-style score; it is still useful as an input
+/// rank list to `reciprocal_rank_fusion`.
+async fn search_qdrant_keyword_candidates(
+    mut filter: Filter,
+    keyword_query: &str,
+    limit: u64,
+) -> Result<Vec<SearchResult>, DefaultError> {
+    filter
+        .must
+        .push(Condition::matches_text("content", keyword_query.to_string()));
+
+    let qdrant = get_qdrant_connection().await?;
+
+    let data = qdrant
+        .scroll(&ScrollPoints {
+            collection_name: COLLECTION_NAME.to_string(),
+            filter: Some(filter),
+            limit: Some(limit as u32),
+            with_payload: None,
+            with_vectors: None,
+            ..Default::default()
+        })
+        .await
+        .map_err(|_e| DefaultError {
+            message: "Failed to keyword search points on Qdrant",
+        })?;
+
+    let point_ids: Vec<SearchResult> = data
+        .result
+        .iter()
+        .filter_map(|point| match point.clone().id?.point_id_options? {
+            PointIdOptions::Uuid(id) => Some(SearchResult {
+                score: 1.0,
+                point_id: uuid::Uuid::parse_str(&id).ok()?,
+                score_details: ScoreDetails {
+                    keyword_score: Some(1.0),
+                    ..Default::default()
+                },
+            }),
+            PointIdOptions::Num(_) => None,
+        })
+        .collect();
+
+    Ok(point_ids)
+}
+
+/// Fuses two independently ranked result lists with Reciprocal Rank Fusion.
+///
+/// For every unique point present in either list, the fused score is
+/// `Σ weight_i * 1 / (RRF_K + rank_i)`, where `rank_i` is the point's 0-based
+/// position in list `i` (lists it is absent from simply don't contribute).
+/// `semantic_weight` biases the fusion toward the semantic list; the keyword
+/// list gets `1.0 - semantic_weight`.
+fn reciprocal_rank_fusion(
+    semantic_results: Vec<SearchResult>,
+    keyword_results: Vec<SearchResult>,
+    semantic_weight: f32,
+) -> Vec<SearchResult> {
+    let keyword_weight = 1.0 - semantic_weight;
+    let mut fused: HashMap<uuid::Uuid, (f32, ScoreDetails)> = HashMap::new();
+
+    for (rank, result) in semantic_results.into_iter().enumerate() {
+        let entry = fused.entry(result.point_id).or_insert((0.0, ScoreDetails::default()));
+        entry.0 += semantic_weight * (1.0 / (RRF_K + rank as f32));
+        entry.1.vector_similarity = result.score_details.vector_similarity;
+    }
+
+    for (rank, result) in keyword_results.into_iter().enumerate() {
+        let entry = fused.entry(result.point_id).or_insert((0.0, ScoreDetails::default()));
+        entry.0 += keyword_weight * (1.0 / (RRF_K + rank as f32));
+        entry.1.keyword_score = result.score_details.keyword_score;
+    }
+
+    let mut fused: Vec<SearchResult> = fused
+        .into_iter()
+        .map(|(point_id, (score, mut score_details))| {
+            score_details.rrf_score = Some(score);
+            SearchResult {
+                point_id,
+                score,
+                score_details,
+            }
+        })
+        .collect();
+
+    fused.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
+
+    fused
+}
+
+#[cfg(test)]
+mod reciprocal_rank_fusion_tests {
+    use super::*;
+
+    fn result(point_id: uuid::Uuid, score: f32) -> SearchResult {
+        SearchResult {
+            point_id,
+            score,
+            score_details: ScoreDetails::default(),
+        }
+    }
+
+    #[test]
+    fn ranks_a_hit_present_in_both_lists_above_one_only_present_in_a_single_list() {
+        let top_both = uuid::Uuid::new_v4();
+        let semantic_only = uuid::Uuid::new_v4();
+        let keyword_only = uuid::Uuid::new_v4();
+
+        let semantic_results = vec![result(top_both, 0.9), result(semantic_only, 0.8)];
+        let keyword_results = vec![result(top_both, 1.0), result(keyword_only, 1.0)];
+
+        let fused = reciprocal_rank_fusion(semantic_results, keyword_results, 0.5);
+
+        assert_eq!(fused[0].point_id, top_both);
+        assert_eq!(fused.len(), 3);
+    }
+
+    #[test]
+    fn semantic_weight_of_one_ignores_keyword_only_hits_score_contribution() {
+        let semantic_top = uuid::Uuid::new_v4();
+        let keyword_top = uuid::Uuid::new_v4();
+
+        let semantic_results = vec![result(semantic_top, 0.9)];
+        let keyword_results = vec![result(keyword_top, 1.0)];
+
+        let fused = reciprocal_rank_fusion(semantic_results, keyword_results, 1.0);
+
+        let semantic_score = fused
+            .iter()
+            .find(|r| r.point_id == semantic_top)
+            .unwrap()
+            .score;
+        let keyword_score = fused
+            .iter()
+            .find(|r| r.point_id == keyword_top)
+            .unwrap()
+            .score;
+
+        assert!(semantic_score > 0.0);
+        assert_eq!(keyword_score, 0.0);
+    }
+}
+
+/// Searches the `debate_cards` collection, optionally blending a keyword query in
+/// with the vector search using Reciprocal Rank Fusion.
+///
+/// `semantic_ratio` controls the blend: `1.0` runs pure vector search (the
+/// historical behavior of this function), values closer to `0.0` weight the
+/// keyword list more heavily. `keyword_query` is ignored when `semantic_ratio`
+/// is `1.0`. Each returned `SearchResult` carries a `ScoreDetails` breakdown of
+/// the signals that contributed to its final score.
+pub async fn search_qdrant_query(
+    page: u64,
+    filter: Filter,
+    embedding_vector: Vec<f32>,
+    keyword_query: Option<String>,
+    semantic_ratio: f32,
+) -> Result<Vec<SearchResult>, DefaultError> {
+    let keyword_query = match keyword_query {
+        Some(keyword_query) if semantic_ratio < 1.0 => keyword_query,
+        _ => return search_qdrant_vector_query(page, filter, embedding_vector).await,
+    };
+
+    // Both signals need to rank over the same candidate pool before fusion, or a
+    // rank computed within one page-sized window isn't comparable to the other's
+    // and a hit outside page 1's top 10 can never surface even if the other
+    // signal would have ranked it highly. Fetch everything up to the end of the
+    // requested page from offset 0, fuse over that, then slice out the page.
+    let candidate_pool_size = page * PAGE_SIZE;
+    let semantic_results =
+        search_qdrant_vector_candidates(filter.clone(), embedding_vector, candidate_pool_size, 0)
+            .await?;
+    let keyword_results =
+        search_qdrant_keyword_candidates(filter, &keyword_query, candidate_pool_size).await?;
+
+    let fused = reciprocal_rank_fusion(semantic_results, keyword_results, semantic_ratio);
+
+    Ok(fused
+        .into_iter()
+        .skip(((page - 1) * PAGE_SIZE) as usize)
+        .take(PAGE_SIZE as usize)
+        .collect())
+}
+
+/// Embeds `text` and upserts it as a new point, so callers don't need to know the
+/// embedding model or dimensionality used by the `debate_cards` collection.
+pub async fn create_new_qdrant_point_from_text(
+    point_id: uuid::Uuid,
+    text: &str,
+    private: bool,
+    author_id: Option<uuid::Uuid>,
+) -> Result<(), actix_web::Error> {
+    let embedding_vector = embed_text(text)
+        .await
+        .map_err(|err| ServiceError::BadRequest(err.message.into()))?;
+
+    create_new_qdrant_point_query(point_id, embedding_vector, private, author_id).await
+}
+
+/// Embeds `text` and runs a pure vector search with it, so callers don't need to
+/// know the embedding model or dimensionality used by the `debate_cards` collection.
+pub async fn search_qdrant_text_query(
+    page: u64,
+    filter: Filter,
+    text: &str,
+) -> Result<Vec<SearchResult>, DefaultError> {
+    let embedding_vector = embed_text(text).await?;
+
+    search_qdrant_query(page, filter, embedding_vector, None, 1.0).await
+}
+
+/// Adds the mandatory private-card access clause to `user_filter`: a card must
+/// either be public (`private == false`) or have `author_id` in its `authors`
+/// list. Public cards are stored with no `private` key at all rather than an
+/// explicit `"private": false` (see `build_card_payload`), so the clause can't
+/// be a simple `should(private == false)` — Qdrant match conditions don't match
+/// a missing field, which would hide every public card. Instead this excludes
+/// the complement: `must_not(private == true AND authors does not contain
+/// author_id)`, which is satisfied by a public card regardless of whether the
+/// field is present or absent. This is `must_not`-ed onto whatever the caller
+/// already wants filtered, so the caller's filter can't be used to bypass it.
+fn scope_filter_to_author(mut user_filter: Filter, author_id: Option<uuid::Uuid>) -> Filter {
+    let mut inaccessible_private_card = Filter::default();
+    inaccessible_private_card
+        .must
+        .push(Condition::matches("private", true));
+
+    if let Some(author_id) = author_id {
+        let mut not_own_card = Filter::default();
+        not_own_card
+            .must_not
+            .push(Condition::matches("authors", author_id.to_string()));
+        inaccessible_private_card
+            .must
+            .push(Condition::filter(not_own_card));
+    }
+
+    user_filter
+        .must_not
+        .push(Condition::filter(inaccessible_private_card));
+    user_filter
+}
+
+/// Runs a vector search scoped to what `author_id` is allowed to see: public
+/// cards plus any private cards they authored. Unlike `search_qdrant_query`,
+/// which enforces nothing beyond the caller-supplied `filter`, this guarantees
+/// the access clause is applied regardless of what `user_filter` contains.
+pub async fn search_qdrant_query_scoped(
+    page: u64,
+    user_filter: Filter,
+    author_id: Option<uuid::Uuid>,
+    embedding_vector: Vec<f32>,
+) -> Result<Vec<SearchResult>, DefaultError> {
+    let scoped_filter = scope_filter_to_author(user_filter, author_id);
+
+    search_qdrant_vector_query(page, scoped_filter, embedding_vector).await
+}
+
+#[cfg(test)]
+mod scope_filter_to_author_tests {
+    use qdrant_client::qdrant::condition::ConditionOneOf;
+
+    use super::*;
+
+    fn nested_filter(condition: &Condition) -> &Filter {
+        match condition.condition_one_of.as_ref().unwrap() {
+            ConditionOneOf::Filter(filter) => filter,
+            _ => panic!("expected a nested Filter condition"),
+        }
+    }
+
+    #[test]
+    fn excludes_inaccessible_private_cards_via_must_not_not_a_should() {
+        // The bug this guards against: a `should(private == false)` clause can
+        // never match a public card, because public cards are stored with no
+        // `private` key at all (see `build_card_payload`). The fix expresses the
+        // access rule as an exclusion instead, which a missing field satisfies.
+        let scoped = scope_filter_to_author(Filter::default(), Some(uuid::Uuid::nil()));
+
+        assert_eq!(scoped.must_not.len(), 1);
+        assert!(scoped.should.is_empty());
+
+        let inaccessible_private_card = nested_filter(&scoped.must_not[0]);
+        assert_eq!(inaccessible_private_card.must.len(), 2);
+    }
+
+    #[test]
+    fn anonymous_caller_still_excludes_all_private_cards() {
+        let scoped = scope_filter_to_author(Filter::default(), None);
+
+        let inaccessible_private_card = nested_filter(&scoped.must_not[0]);
+        // No author to except, so the only requirement for exclusion is
+        // `private == true` - every private card is inaccessible.
+        assert_eq!(inaccessible_private_card.must.len(), 1);
+    }
+
+    #[test]
+    fn preserves_the_callers_existing_filter() {
+        let mut user_filter = Filter::default();
+        user_filter.must.push(Condition::matches("topic", "tax".to_string()));
+
+        let scoped = scope_filter_to_author(user_filter, Some(uuid::Uuid::nil()));
+
+        assert_eq!(scoped.must.len(), 1);
+    }
+}